@@ -0,0 +1,358 @@
+use super::*;
+
+// Execution path for `set pty := true`: runs a command behind a pseudo
+// terminal instead of plain pipes, so programs that probe `isatty` (color,
+// progress bars, prompts) behave as they would in a real terminal.
+pub(crate) struct PtyCommand {
+  command: Command,
+}
+
+impl PtyCommand {
+  pub(crate) fn new(command: Command) -> Self {
+    Self { command }
+  }
+
+  pub(crate) fn available() -> bool {
+    #[cfg(unix)]
+    {
+      unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 }
+    }
+
+    #[cfg(windows)]
+    {
+      unsafe {
+        let mut mode = 0;
+        let handle = windows_sys::Win32::System::Console::GetStdHandle(
+          windows_sys::Win32::System::Console::STD_OUTPUT_HANDLE,
+        );
+        windows_sys::Win32::System::Console::GetConsoleMode(handle, &mut mode) != 0
+      }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+      false
+    }
+  }
+
+  // Falls back to `self.command.status()` (untouched: the PTY path below
+  // never runs on `self.command` itself) whenever a PTY isn't available or
+  // setting one up fails.
+  pub(crate) fn status(mut self) -> io::Result<ExitStatus> {
+    if !Self::available() {
+      return self.command.status();
+    }
+
+    match self.run_in_pty() {
+      Ok(status) => Ok(status),
+      Err(_) => self.command.status(),
+    }
+  }
+
+  #[cfg(unix)]
+  fn run_in_pty(&mut self) -> io::Result<ExitStatus> {
+    unix::run(&mut Self::clone_command(&self.command))
+  }
+
+  #[cfg(windows)]
+  fn run_in_pty(&mut self) -> io::Result<ExitStatus> {
+    windows::run(&mut Self::clone_command(&self.command))
+  }
+
+  #[cfg(not(any(unix, windows)))]
+  fn run_in_pty(&mut self) -> io::Result<ExitStatus> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "PTYs are not supported on this platform",
+    ))
+  }
+
+  // `Command` has no `Clone`, and the PTY path needs to wire up stdio and a
+  // `pre_exec` hook of its own — doing that on `self.command` directly
+  // would leave it unusable for the pipe fallback above if spawning fails.
+  #[cfg(any(unix, windows))]
+  fn clone_command(command: &Command) -> Command {
+    let mut clone = Command::new(command.get_program());
+
+    clone.args(command.get_args());
+
+    for (key, value) in command.get_envs() {
+      match value {
+        Some(value) => {
+          clone.env(key, value);
+        }
+        None => {
+          clone.env_remove(key);
+        }
+      }
+    }
+
+    if let Some(dir) = command.get_current_dir() {
+      clone.current_dir(dir);
+    }
+
+    clone
+  }
+}
+
+#[cfg(unix)]
+mod unix {
+  use super::*;
+
+  pub(super) fn run(command: &mut Command) -> io::Result<ExitStatus> {
+    let (master, slave) = open_pty()?;
+
+    let spawned = copy_window_size(&master).and_then(|resize| {
+      // SAFETY: `dup` duplicates a valid, open file descriptor.
+      unsafe {
+        command
+          .stdin(std::process::Stdio::from_raw_fd(libc::dup(slave)))
+          .stdout(std::process::Stdio::from_raw_fd(libc::dup(slave)))
+          .stderr(std::process::Stdio::from_raw_fd(libc::dup(slave)));
+
+        command.pre_exec(|| {
+          if libc::setsid() < 0 {
+            return Err(io::Error::last_os_error());
+          }
+
+          // A session leader only gets a controlling terminal automatically
+          // by open()ing one it doesn't already have; the slave here was
+          // opened by the parent, so we have to claim it explicitly.
+          if libc::ioctl(libc::STDIN_FILENO, libc::TIOCSCTTY, 0) < 0 {
+            return Err(io::Error::last_os_error());
+          }
+
+          Ok(())
+        });
+      }
+
+      command.spawn().map(|child| (child, resize)).map_err(|err| {
+        signal_hook_registry::unregister(resize);
+        err
+      })
+    });
+
+    // SAFETY: `slave` is only otherwise referenced by the dup'd copies
+    // handed to the child's stdio above.
+    unsafe {
+      libc::close(slave);
+    }
+
+    let (mut child, resize) = match spawned {
+      Ok(spawned) => spawned,
+      Err(err) => {
+        // SAFETY: `master` is an open fd owned by this function.
+        unsafe {
+          libc::close(master);
+        }
+        return Err(err);
+      }
+    };
+
+    forward_until_child_exits(libc::STDIN_FILENO, master, libc::STDOUT_FILENO);
+
+    let status = child.wait();
+
+    signal_hook_registry::unregister(resize);
+
+    // SAFETY: both directions in `forward_until_child_exits` have returned,
+    // so nothing else references `master`.
+    unsafe {
+      libc::close(master);
+    }
+
+    status
+  }
+
+  fn open_pty() -> io::Result<(RawFd, RawFd)> {
+    let mut master: RawFd = 0;
+    let mut slave: RawFd = 0;
+
+    // SAFETY: out-pointers are valid; null requests default termios/winsize.
+    let result = unsafe {
+      libc::openpty(
+        &mut master,
+        &mut slave,
+        ptr::null_mut(),
+        ptr::null_mut(),
+        ptr::null(),
+      )
+    };
+
+    if result != 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    Ok((master, slave))
+  }
+
+  // Copies the parent terminal's window size onto the PTY, keeping it in
+  // sync across `SIGWINCH` until the returned handler is unregistered.
+  fn copy_window_size(master: &RawFd) -> io::Result<signal_hook_registry::SigId> {
+    set_window_size(*master)?;
+
+    let master = *master;
+    // SAFETY: the registered handler only does an async-signal-safe ioctl.
+    unsafe {
+      signal_hook_registry::register(libc::SIGWINCH, move || {
+        let _ = set_window_size(master);
+      })
+    }
+  }
+
+  fn set_window_size(master: RawFd) -> io::Result<()> {
+    let mut size: libc::winsize = unsafe { mem::zeroed() };
+
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    if unsafe { libc::ioctl(master, libc::TIOCSWINSZ, &size) } != 0 {
+      return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+  }
+
+  // Multiplexes `input_fd` -> `master` and `master` -> `output_fd` on this
+  // thread, via `poll(2)`, until `master` hits EOF/HUP (the child exited
+  // and the kernel closed the slave-side fds duped into it). No background
+  // thread survives past this call returning: an earlier recipe's leaked
+  // stdin reader would otherwise be free to steal the first input bytes of
+  // whatever recipe runs next.
+  pub(super) fn forward_until_child_exits(input_fd: RawFd, master: RawFd, output_fd: RawFd) {
+    let mut input_buf = [0u8; 4096];
+    let mut master_buf = [0u8; 4096];
+
+    let mut fds = [
+      libc::pollfd {
+        fd: input_fd,
+        events: libc::POLLIN,
+        revents: 0,
+      },
+      libc::pollfd {
+        fd: master,
+        events: libc::POLLIN,
+        revents: 0,
+      },
+    ];
+
+    loop {
+      // SAFETY: `fds` is a valid, appropriately-sized array of pollfds.
+      let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+
+      if ready < 0 {
+        break;
+      }
+
+      if fds[1].revents & (libc::POLLIN | libc::POLLHUP | libc::POLLERR) != 0 {
+        // SAFETY: `master_buf` is valid for `master_buf.len()` bytes.
+        let n = unsafe {
+          libc::read(
+            master,
+            master_buf.as_mut_ptr().cast(),
+            master_buf.len(),
+          )
+        };
+
+        if n <= 0 {
+          break;
+        }
+
+        // SAFETY: `output_fd` is open for the caller's lifetime and
+        // `master_buf[..n]` was just initialized by the read above.
+        unsafe {
+          libc::write(output_fd, master_buf.as_ptr().cast(), n as usize);
+        }
+      }
+
+      if fds[0].revents & libc::POLLIN != 0 {
+        // SAFETY: `input_buf` is valid for `input_buf.len()` bytes.
+        let n = unsafe {
+          libc::read(input_fd, input_buf.as_mut_ptr().cast(), input_buf.len())
+        };
+
+        if n > 0 {
+          // SAFETY: `master` is open and `input_buf[..n]` was just read.
+          unsafe {
+            libc::write(master, input_buf.as_ptr().cast(), n as usize);
+          }
+        }
+      }
+
+      fds[0].revents = 0;
+      fds[1].revents = 0;
+    }
+  }
+}
+
+#[cfg(windows)]
+mod windows {
+  use super::*;
+
+  // ConPTY setup (CreatePseudoConsole + a STARTUPINFOEX attribute list) is
+  // sizable enough that it's stubbed out until `just` ships a Windows PTY
+  // backend; callers fall back to the plain pipe path on any error here.
+  pub(super) fn run(_command: &mut Command) -> io::Result<ExitStatus> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "ConPTY support is not yet implemented",
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(unix)]
+  fn pipe() -> (RawFd, RawFd) {
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    (fds[0], fds[1])
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn forward_until_child_exits_relays_master_output_then_stops_at_eof() {
+    let (input_read, input_write) = pipe();
+    let (master_read, master_write) = pipe();
+    let (output_read, output_write) = pipe();
+
+    unsafe {
+      libc::write(master_write, b"hi".as_ptr().cast(), 2);
+      libc::close(master_write);
+    }
+
+    unix::forward_until_child_exits(input_read, master_read, output_write);
+
+    let mut buf = [0u8; 2];
+    assert_eq!(unsafe { libc::read(output_read, buf.as_mut_ptr().cast(), 2) }, 2);
+    assert_eq!(&buf, b"hi");
+
+    unsafe {
+      libc::close(input_read);
+      libc::close(input_write);
+      libc::close(master_read);
+      libc::close(output_read);
+      libc::close(output_write);
+    }
+  }
+
+  #[test]
+  fn status_falls_back_to_pipes_when_pty_unavailable() {
+    // `available()` checks whether fd 1 is a terminal. libtest's output
+    // capture does not redirect fd 1, so this holds only when the test
+    // binary's stdout is not a terminal (piped, or under CI).
+    assert!(!PtyCommand::available());
+
+    let mut command = Command::new(if cfg!(windows) { "cmd" } else { "true" });
+    if cfg!(windows) {
+      command.args(["/C", "exit 0"]);
+    }
+
+    let status = PtyCommand::new(command).status().unwrap();
+
+    assert!(status.success());
+  }
+}
@@ -4,6 +4,13 @@ pub(crate) const DEFAULT_SHELL: &str = "sh";
 pub(crate) const DEFAULT_SHELL_ARGS: &[&str] = &["-cu"];
 pub(crate) const WINDOWS_POWERSHELL_SHELL: &str = "powershell.exe";
 pub(crate) const WINDOWS_POWERSHELL_ARGS: &[&str] = &["-NoLogo", "-Command"];
+pub(crate) const WINDOWS_CMD_SHELL: &str = "cmd.exe";
+pub(crate) const WINDOWS_CMD_ARGS: &[&str] = &["/C"];
+
+// Rejected outside of quotes under `set no-shell`, since there's no shell to
+// interpret them. Not exhaustive: glob/expansion characters (`*`, `?`, `~`,
+// `=`, braces) still pass through unrejected and are exec'd as literal argv.
+const SHELL_METACHARACTERS: &[char] = &['|', '>', '<', '&', ';', '$', '`'];
 
 #[derive(Debug, PartialEq, Serialize, Default)]
 pub(crate) struct Settings<'src> {
@@ -12,14 +19,191 @@ pub(crate) struct Settings<'src> {
   pub(crate) export: bool,
   pub(crate) fallback: bool,
   pub(crate) ignore_comments: bool,
+  pub(crate) no_shell: bool,
   pub(crate) positional_arguments: bool,
+  pub(crate) pty: bool,
   pub(crate) shell: Option<Shell<'src>>,
   pub(crate) tempdir: Option<String>,
+  pub(crate) windows_cmd: bool,
   pub(crate) windows_powershell: bool,
   pub(crate) windows_shell: Option<Shell<'src>>,
 }
 
+#[derive(Debug, PartialEq)]
+pub(crate) enum ShellInvocation<'a> {
+  Shell { command: &'a str, args: Vec<&'a str> },
+  None,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ShellRecipeLineError {
+  Empty { line: String },
+  UnterminatedQuote { line: String },
+  ShellMetacharacter { line: String, character: char },
+}
+
+impl std::fmt::Display for ShellRecipeLineError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Empty { line } => write!(f, "recipe line `{line}` has no program to execute"),
+      Self::UnterminatedQuote { line } => {
+        write!(f, "recipe line `{line}` contains an unterminated quote")
+      }
+      Self::ShellMetacharacter { line, character } => write!(
+        f,
+        "recipe line `{line}` contains `{character}`, which has no effect under `set no-shell` \
+         since there is no shell to interpret it",
+      ),
+    }
+  }
+}
+
+impl std::error::Error for ShellRecipeLineError {}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct ConfigFile {
+  dotenv_load: Option<bool>,
+  shell: Option<Vec<String>>,
+  tempdir: Option<String>,
+}
+
+impl ConfigFile {
+  // Empty `shell` is an error rather than a silent fallback to the
+  // justfile's own shell setting, which would mask what's almost always a
+  // config file mistake.
+  fn into_settings(self, path: &std::path::Path) -> Result<Settings<'static>, ConfigFileError> {
+    let shell = match self.shell {
+      Some(shell) => {
+        let (command, arguments) = shell
+          .split_first()
+          .ok_or_else(|| ConfigFileError::EmptyShell {
+            path: path.to_owned(),
+          })?;
+
+        Some(Shell {
+          command: Self::string_literal(command),
+          arguments: arguments.iter().map(|arg| Self::string_literal(arg)).collect(),
+        })
+      }
+      None => None,
+    };
+
+    Ok(Settings {
+      dotenv_load: self.dotenv_load,
+      shell,
+      tempdir: self.tempdir,
+      ..Settings::default()
+    })
+  }
+
+  // Leaked to obtain a `'static` string slice, since a `Shell<'static>` is a
+  // valid `Shell<'src>` for any justfile-derived `'src`. Only sound because
+  // `from_config_file` runs at most once per process, which debug builds
+  // assert; see there.
+  fn string_literal(s: &str) -> StringLiteral<'static> {
+    let leaked: &'static str = Box::leak(s.to_owned().into_boxed_str());
+
+    StringLiteral {
+      kind: StringKind::from_token_start("\"").unwrap(),
+      raw: leaked,
+      cooked: leaked.to_owned(),
+    }
+  }
+}
+
+#[derive(Debug)]
+pub(crate) enum ConfigFileError {
+  Io {
+    path: std::path::PathBuf,
+    io_error: std::io::Error,
+  },
+  Toml {
+    path: std::path::PathBuf,
+    toml_error: toml::de::Error,
+  },
+  EmptyShell {
+    path: std::path::PathBuf,
+  },
+}
+
+impl std::fmt::Display for ConfigFileError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Self::Io { path, io_error } => {
+        write!(f, "failed to read config file `{}`: {io_error}", path.display())
+      }
+      Self::Toml { path, toml_error } => {
+        write!(
+          f,
+          "failed to parse config file `{}`: {toml_error}",
+          path.display()
+        )
+      }
+      Self::EmptyShell { path } => {
+        write!(
+          f,
+          "config file `{}` set `shell` to an empty array",
+          path.display()
+        )
+      }
+    }
+  }
+}
+
+impl std::error::Error for ConfigFileError {}
+
 impl<'src> Settings<'src> {
+  // `ConfigFile::string_literal` leaks its strings to manufacture the
+  // `'static` lifetime `Settings<'static>` needs; that's only sound as long
+  // as a justfile invocation loads at most one config file, so debug builds
+  // assert it rather than leaking unboundedly if that assumption ever breaks
+  // (e.g. a long-lived process calling this in a loop). Release builds do not
+  // check it.
+  pub(crate) fn from_config_file(
+    path: &std::path::Path,
+  ) -> Result<Settings<'static>, ConfigFileError> {
+    static LOADED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    debug_assert!(
+      !LOADED.swap(true, std::sync::atomic::Ordering::Relaxed),
+      "from_config_file must be called at most once per process",
+    );
+
+    let contents = std::fs::read_to_string(path).map_err(|io_error| ConfigFileError::Io {
+      path: path.to_owned(),
+      io_error,
+    })?;
+
+    let file: ConfigFile = toml::from_str(&contents).map_err(|toml_error| ConfigFileError::Toml {
+      path: path.to_owned(),
+      toml_error,
+    })?;
+
+    file.into_settings(path)
+  }
+
+  // A field only falls back to `other` when `self` is still at its default
+  // value — the same limitation `set` directives already have when two of
+  // them disagree, since there's no way to distinguish "explicitly set to
+  // false" from "never mentioned".
+  pub(crate) fn merge(self, other: Settings<'src>) -> Settings<'src> {
+    Settings {
+      allow_duplicate_recipes: self.allow_duplicate_recipes || other.allow_duplicate_recipes,
+      dotenv_load: self.dotenv_load.or(other.dotenv_load),
+      export: self.export || other.export,
+      fallback: self.fallback || other.fallback,
+      ignore_comments: self.ignore_comments || other.ignore_comments,
+      no_shell: self.no_shell || other.no_shell,
+      positional_arguments: self.positional_arguments || other.positional_arguments,
+      pty: self.pty || other.pty,
+      shell: self.shell.or(other.shell),
+      tempdir: self.tempdir.or(other.tempdir),
+      windows_cmd: self.windows_cmd || other.windows_cmd,
+      windows_powershell: self.windows_powershell || other.windows_powershell,
+      windows_shell: self.windows_shell.or(other.windows_shell),
+    }
+  }
+
   pub(crate) fn from_setting_iter(iter: impl Iterator<Item = Setting<'src>>) -> Self {
     let mut settings = Self::default();
 
@@ -40,12 +224,21 @@ impl<'src> Settings<'src> {
         Setting::IgnoreComments(ignore_comments) => {
           settings.ignore_comments = ignore_comments;
         }
+        Setting::NoShell(no_shell) => {
+          settings.no_shell = no_shell;
+        }
         Setting::PositionalArguments(positional_arguments) => {
           settings.positional_arguments = positional_arguments;
         }
+        Setting::Pty(pty) => {
+          settings.pty = pty;
+        }
         Setting::Shell(shell) => {
           settings.shell = Some(shell);
         }
+        Setting::WindowsCmd(windows_cmd) => {
+          settings.windows_cmd = windows_cmd;
+        }
         Setting::WindowsPowerShell(windows_powershell) => {
           settings.windows_powershell = windows_powershell;
         }
@@ -61,51 +254,138 @@ impl<'src> Settings<'src> {
     settings
   }
 
-  pub(crate) fn shell_command(&self, config: &Config) -> Command {
-    let (command, args) = self.shell(config);
-
-    let mut cmd = Command::new(command);
+  pub(crate) fn shell_command(
+    &self,
+    config: &Config,
+    line: &str,
+  ) -> Result<Command, ShellRecipeLineError> {
+    match self.shell(config) {
+      ShellInvocation::Shell { command, args } => {
+        let mut cmd = Command::new(command);
+        cmd.args(args);
+        cmd.arg(line);
+        Ok(cmd)
+      }
+      ShellInvocation::None => {
+        let mut argv = Self::tokenize_argv(line)?.into_iter();
 
-    cmd.args(args);
+        let program = argv.next().ok_or_else(|| ShellRecipeLineError::Empty {
+          line: line.to_owned(),
+        })?;
 
-    cmd
+        let mut cmd = Command::new(program);
+        cmd.args(argv);
+        Ok(cmd)
+      }
+    }
   }
 
-  pub(crate) fn shell<'a>(&'a self, config: &'a Config) -> (&'a str, Vec<&'a str>) {
+  pub(crate) fn shell<'a>(&'a self, config: &'a Config) -> ShellInvocation<'a> {
     match (&config.shell, &config.shell_args) {
-      (Some(shell), Some(shell_args)) => (shell, shell_args.iter().map(String::as_ref).collect()),
-      (Some(shell), None) => (shell, DEFAULT_SHELL_ARGS.to_vec()),
-      (None, Some(shell_args)) => (
-        DEFAULT_SHELL,
-        shell_args.iter().map(String::as_ref).collect(),
-      ),
+      (Some(shell), Some(shell_args)) => ShellInvocation::Shell {
+        command: shell,
+        args: shell_args.iter().map(String::as_ref).collect(),
+      },
+      (Some(shell), None) => ShellInvocation::Shell {
+        command: shell,
+        args: DEFAULT_SHELL_ARGS.to_vec(),
+      },
+      (None, Some(shell_args)) => ShellInvocation::Shell {
+        command: DEFAULT_SHELL,
+        args: shell_args.iter().map(String::as_ref).collect(),
+      },
       (None, None) => {
-        if let (true, Some(shell)) = (cfg!(windows), &self.windows_shell) {
-          (
-            shell.command.cooked.as_ref(),
-            shell
+        if self.no_shell {
+          ShellInvocation::None
+        } else if let (true, Some(shell)) = (cfg!(windows), &self.windows_shell) {
+          ShellInvocation::Shell {
+            command: shell.command.cooked.as_ref(),
+            args: shell
               .arguments
               .iter()
               .map(|argument| argument.cooked.as_ref())
               .collect(),
-          )
+          }
         } else if cfg!(windows) && self.windows_powershell {
-          (WINDOWS_POWERSHELL_SHELL, WINDOWS_POWERSHELL_ARGS.to_vec())
+          ShellInvocation::Shell {
+            command: WINDOWS_POWERSHELL_SHELL,
+            args: WINDOWS_POWERSHELL_ARGS.to_vec(),
+          }
+        } else if cfg!(windows) && self.windows_cmd {
+          ShellInvocation::Shell {
+            command: WINDOWS_CMD_SHELL,
+            args: WINDOWS_CMD_ARGS.to_vec(),
+          }
         } else if let Some(shell) = &self.shell {
-          (
-            shell.command.cooked.as_ref(),
-            shell
+          ShellInvocation::Shell {
+            command: shell.command.cooked.as_ref(),
+            args: shell
               .arguments
               .iter()
               .map(|argument| argument.cooked.as_ref())
               .collect(),
-          )
+          }
         } else {
-          (DEFAULT_SHELL, DEFAULT_SHELL_ARGS.to_vec())
+          ShellInvocation::Shell {
+            command: DEFAULT_SHELL,
+            args: DEFAULT_SHELL_ARGS.to_vec(),
+          }
         }
       }
     }
   }
+
+  // Quote-aware argv split for `set no-shell`; a metacharacter in
+  // `SHELL_METACHARACTERS` outside of quotes is a hard error rather than
+  // being passed through and silently doing the wrong thing.
+  fn tokenize_argv(line: &str) -> Result<Vec<String>, ShellRecipeLineError> {
+    let mut argv = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+      match c {
+        '\'' | '"' => {
+          in_token = true;
+          let quote = c;
+          loop {
+            match chars.next() {
+              Some(c) if c == quote => break,
+              Some(c) => current.push(c),
+              None => {
+                return Err(ShellRecipeLineError::UnterminatedQuote {
+                  line: line.to_owned(),
+                })
+              }
+            }
+          }
+        }
+        c if c.is_whitespace() => {
+          if in_token {
+            argv.push(std::mem::take(&mut current));
+            in_token = false;
+          }
+        }
+        c if SHELL_METACHARACTERS.contains(&c) => {
+          return Err(ShellRecipeLineError::ShellMetacharacter {
+            line: line.to_owned(),
+            character: c,
+          });
+        }
+        c => {
+          in_token = true;
+          current.push(c);
+        }
+      }
+    }
+
+    if in_token {
+      argv.push(current);
+    }
+
+    Ok(argv)
+  }
 }
 
 #[cfg(test)]
@@ -121,7 +401,13 @@ mod tests {
       ..testing::config(&[])
     };
 
-    assert_eq!(settings.shell(&config), ("sh", vec!["-cu"]));
+    assert_eq!(
+      settings.shell(&config),
+      ShellInvocation::Shell {
+        command: "sh",
+        args: vec!["-cu"]
+      }
+    );
   }
 
   #[test]
@@ -139,10 +425,50 @@ mod tests {
     if cfg!(windows) {
       assert_eq!(
         settings.shell(&config),
-        ("powershell.exe", vec!["-NoLogo", "-Command"])
+        ShellInvocation::Shell {
+          command: "powershell.exe",
+          args: vec!["-NoLogo", "-Command"]
+        }
       );
     } else {
-      assert_eq!(settings.shell(&config), ("sh", vec!["-cu"]));
+      assert_eq!(
+        settings.shell(&config),
+        ShellInvocation::Shell {
+          command: "sh",
+          args: vec!["-cu"]
+        }
+      );
+    }
+  }
+
+  #[test]
+  fn default_shell_windows_cmd() {
+    let settings = Settings {
+      windows_cmd: true,
+      ..Default::default()
+    };
+
+    let config = Config {
+      shell_command: false,
+      ..testing::config(&[])
+    };
+
+    if cfg!(windows) {
+      assert_eq!(
+        settings.shell(&config),
+        ShellInvocation::Shell {
+          command: "cmd.exe",
+          args: vec!["/C"]
+        }
+      );
+    } else {
+      assert_eq!(
+        settings.shell(&config),
+        ShellInvocation::Shell {
+          command: "sh",
+          args: vec!["-cu"]
+        }
+      );
     }
   }
 
@@ -157,7 +483,13 @@ mod tests {
       ..testing::config(&[])
     };
 
-    assert_eq!(settings.shell(&config), ("lol", vec!["-nice"]));
+    assert_eq!(
+      settings.shell(&config),
+      ShellInvocation::Shell {
+        command: "lol",
+        args: vec!["-nice"]
+      }
+    );
   }
 
   #[test]
@@ -174,7 +506,13 @@ mod tests {
       ..testing::config(&[])
     };
 
-    assert_eq!(settings.shell(&config), ("lol", vec!["-nice"]));
+    assert_eq!(
+      settings.shell(&config),
+      ShellInvocation::Shell {
+        command: "lol",
+        args: vec!["-nice"]
+      }
+    );
   }
 
   #[test]
@@ -200,7 +538,13 @@ mod tests {
       ..testing::config(&[])
     };
 
-    assert_eq!(settings.shell(&config), ("asdf.exe", vec!["-nope"]));
+    assert_eq!(
+      settings.shell(&config),
+      ShellInvocation::Shell {
+        command: "asdf.exe",
+        args: vec!["-nope"]
+      }
+    );
   }
 
   #[test]
@@ -215,7 +559,13 @@ mod tests {
       ..testing::config(&[])
     };
 
-    assert_eq!(settings.shell(&config).0, "lol");
+    assert_eq!(
+      settings.shell(&config),
+      ShellInvocation::Shell {
+        command: "lol",
+        args: DEFAULT_SHELL_ARGS.to_vec()
+      }
+    );
   }
 
   #[test]
@@ -231,6 +581,259 @@ mod tests {
       ..testing::config(&[])
     };
 
-    assert_eq!(settings.shell(&config), ("sh", vec!["-nice"]));
+    assert_eq!(
+      settings.shell(&config),
+      ShellInvocation::Shell {
+        command: "sh",
+        args: vec!["-nice"]
+      }
+    );
+  }
+
+  #[test]
+  fn no_shell_resolves_to_none() {
+    let settings = Settings {
+      no_shell: true,
+      ..Default::default()
+    };
+
+    let config = Config {
+      shell_command: false,
+      ..testing::config(&[])
+    };
+
+    assert_eq!(settings.shell(&config), ShellInvocation::None);
+  }
+
+  #[test]
+  fn no_shell_loses_to_cli_shell_override() {
+    let settings = Settings {
+      no_shell: true,
+      ..Default::default()
+    };
+
+    let config = Config {
+      shell_command: true,
+      shell: Some("lol".to_string()),
+      shell_args: Some(vec!["-nice".to_string()]),
+      ..testing::config(&[])
+    };
+
+    assert_eq!(
+      settings.shell(&config),
+      ShellInvocation::Shell {
+        command: "lol",
+        args: vec!["-nice"]
+      }
+    );
+  }
+
+  #[test]
+  fn tokenize_argv_splits_on_whitespace() {
+    assert_eq!(
+      Settings::tokenize_argv("cargo build --release").unwrap(),
+      vec!["cargo", "build", "--release"],
+    );
+  }
+
+  #[test]
+  fn tokenize_argv_respects_quotes() {
+    assert_eq!(
+      Settings::tokenize_argv("echo 'hello world' \"a b\"").unwrap(),
+      vec!["echo", "hello world", "a b"],
+    );
+  }
+
+  #[test]
+  fn tokenize_argv_rejects_pipeline() {
+    assert_eq!(
+      Settings::tokenize_argv("echo hi | cat").unwrap_err(),
+      ShellRecipeLineError::ShellMetacharacter {
+        line: "echo hi | cat".into(),
+        character: '|',
+      },
+    );
+  }
+
+  #[test]
+  fn tokenize_argv_rejects_unterminated_quote() {
+    assert_eq!(
+      Settings::tokenize_argv("echo 'hi").unwrap_err(),
+      ShellRecipeLineError::UnterminatedQuote {
+        line: "echo 'hi".into(),
+      },
+    );
+  }
+
+  #[test]
+  fn tokenize_argv_returns_empty_for_blank_line() {
+    assert_eq!(
+      Settings::tokenize_argv("   ").unwrap(),
+      Vec::<String>::new(),
+    );
+  }
+
+  #[test]
+  fn shell_command_wraps_line_in_shell() {
+    let settings = Settings::default();
+
+    let config = Config {
+      shell_command: false,
+      ..testing::config(&[])
+    };
+
+    let cmd = settings.shell_command(&config, "echo hi").unwrap();
+
+    assert_eq!(cmd.get_program(), "sh");
+    assert_eq!(
+      cmd.get_args().collect::<Vec<_>>(),
+      vec!["-cu", "echo hi"],
+    );
+  }
+
+  #[test]
+  fn shell_command_no_shell_execs_argv_directly() {
+    let settings = Settings {
+      no_shell: true,
+      ..Default::default()
+    };
+
+    let config = Config {
+      shell_command: false,
+      ..testing::config(&[])
+    };
+
+    let cmd = settings
+      .shell_command(&config, "cargo build --release")
+      .unwrap();
+
+    assert_eq!(cmd.get_program(), "cargo");
+    assert_eq!(
+      cmd.get_args().collect::<Vec<_>>(),
+      vec!["build", "--release"],
+    );
+  }
+
+  #[test]
+  fn shell_command_no_shell_rejects_empty_line() {
+    let settings = Settings {
+      no_shell: true,
+      ..Default::default()
+    };
+
+    let config = Config {
+      shell_command: false,
+      ..testing::config(&[])
+    };
+
+    assert_eq!(
+      settings.shell_command(&config, "   ").unwrap_err(),
+      ShellRecipeLineError::Empty {
+        line: "   ".into(),
+      },
+    );
+  }
+
+  #[test]
+  fn config_file_settings_lose_to_justfile_settings() {
+    let justfile = Settings {
+      tempdir: Some("from-justfile".into()),
+      ..Default::default()
+    };
+
+    let file = ConfigFile {
+      tempdir: Some("from-file".into()),
+      ..Default::default()
+    }
+    .into_settings(std::path::Path::new("test.toml"))
+    .unwrap();
+
+    let merged = justfile.merge(file);
+
+    assert_eq!(merged.tempdir, Some("from-justfile".to_string()));
+  }
+
+  #[test]
+  fn config_file_settings_fill_in_unset_justfile_settings() {
+    let justfile = Settings::default();
+
+    let file = ConfigFile {
+      dotenv_load: Some(true),
+      tempdir: Some("from-file".into()),
+      ..Default::default()
+    }
+    .into_settings(std::path::Path::new("test.toml"))
+    .unwrap();
+
+    let merged = justfile.merge(file);
+
+    assert_eq!(merged.dotenv_load, Some(true));
+    assert_eq!(merged.tempdir, Some("from-file".to_string()));
+  }
+
+  #[test]
+  fn config_file_shell_loses_to_cli_shell() {
+    let file = ConfigFile {
+      shell: Some(vec!["asdf.exe".into(), "-nope".into()]),
+      ..Default::default()
+    }
+    .into_settings(std::path::Path::new("test.toml"))
+    .unwrap();
+
+    let merged = Settings::default().merge(file);
+
+    let config = Config {
+      shell_command: true,
+      shell: Some("lol".to_string()),
+      shell_args: Some(vec!["-nice".to_string()]),
+      ..testing::config(&[])
+    };
+
+    assert_eq!(
+      merged.shell(&config),
+      ShellInvocation::Shell {
+        command: "lol",
+        args: vec!["-nice"]
+      }
+    );
+  }
+
+  #[test]
+  fn config_file_shell_applies_when_no_cli_override() {
+    let file = ConfigFile {
+      shell: Some(vec!["asdf.exe".into(), "-nope".into()]),
+      ..Default::default()
+    }
+    .into_settings(std::path::Path::new("test.toml"))
+    .unwrap();
+
+    let merged = Settings::default().merge(file);
+
+    let config = Config {
+      shell_command: false,
+      ..testing::config(&[])
+    };
+
+    assert_eq!(
+      merged.shell(&config),
+      ShellInvocation::Shell {
+        command: "asdf.exe",
+        args: vec!["-nope"]
+      }
+    );
+  }
+
+  #[test]
+  fn config_file_rejects_empty_shell() {
+    let path = std::path::Path::new("test.toml");
+
+    let err = ConfigFile {
+      shell: Some(Vec::new()),
+      ..Default::default()
+    }
+    .into_settings(path)
+    .unwrap_err();
+
+    assert!(matches!(err, ConfigFileError::EmptyShell { path: p } if p == path));
   }
 }